@@ -0,0 +1,108 @@
+//! Optional battery/UPS metrics, gated behind the `BATTERY_ENABLED` env var
+//! (or `enabled` in the config file's `[collector.battery]` table).
+//!
+//! Harvested through the `battery` crate (the same cross-platform battery
+//! backend `bottom` uses) rather than raw sysfs, so this degrades gracefully
+//! on devices that report no battery at all.
+
+use std::error::Error;
+
+use battery::units::electric_potential::volt;
+use battery::units::energy::watt_hour;
+use battery::units::power::watt;
+use battery::units::ratio::percent;
+use battery::{Manager, State};
+use prometheus_exporter::prometheus::{register_gauge_vec, GaugeVec};
+
+const STATES: &[(&str, State)] = &[
+    ("charging", State::Charging),
+    ("discharging", State::Discharging),
+    ("full", State::Full),
+    ("empty", State::Empty),
+    ("unknown", State::Unknown),
+];
+
+/// Registered `GaugeVec`s for the battery subsystem, keyed by battery identifier.
+pub struct BatteryMetrics {
+    manager: Manager,
+    charge_percent: GaugeVec,
+    energy_watt_hours: GaugeVec,
+    voltage_volts: GaugeVec,
+    rate_watts: GaugeVec,
+    state: GaugeVec,
+}
+
+impl BatteryMetrics {
+    /// Register the battery gauges and open a `battery::Manager`.
+    pub fn register() -> Result<Self, Box<dyn Error>> {
+        Ok(BatteryMetrics {
+            manager: Manager::new()?,
+            charge_percent: register_gauge_vec!(
+                "battery_charge_percent",
+                "Remaining battery charge in percent",
+                &["battery"]
+            )?,
+            energy_watt_hours: register_gauge_vec!(
+                "battery_energy_watt_hours",
+                "Remaining battery energy in Wh",
+                &["battery"]
+            )?,
+            voltage_volts: register_gauge_vec!(
+                "battery_voltage_volts",
+                "Battery voltage in volts",
+                &["battery"]
+            )?,
+            rate_watts: register_gauge_vec!(
+                "battery_rate_watts",
+                "Battery charge (positive) or discharge (negative) rate in watts",
+                &["battery"]
+            )?,
+            state: register_gauge_vec!(
+                "battery_state",
+                "1 for the battery's current state, 0 otherwise",
+                &["battery", "state"]
+            )?,
+        })
+    }
+
+    /// Refresh every battery reported by the system.
+    pub fn update(&self) {
+        let batteries = match self.manager.batteries() {
+            Ok(batteries) => batteries,
+            Err(e) => {
+                eprintln!("Failed to enumerate batteries: {:?}", e);
+                return;
+            }
+        };
+
+        for (index, battery) in batteries.flatten().enumerate() {
+            let id = battery
+                .serial_number()
+                .map(str::to_owned)
+                .unwrap_or_else(|| index.to_string());
+
+            self.charge_percent
+                .with_label_values(&[&id])
+                .set(battery.state_of_charge().get::<percent>() as f64);
+            self.energy_watt_hours
+                .with_label_values(&[&id])
+                .set(battery.energy().get::<watt_hour>() as f64);
+            self.voltage_volts
+                .with_label_values(&[&id])
+                .set(battery.voltage().get::<volt>() as f64);
+
+            let rate = battery.energy_rate().get::<watt>() as f64;
+            let signed_rate = match battery.state() {
+                State::Discharging => -rate,
+                _ => rate,
+            };
+            self.rate_watts.with_label_values(&[&id]).set(signed_rate);
+
+            for (name, state) in STATES {
+                self.state
+                    .with_label_values(&[&id, name])
+                    .set(if battery.state() == *state { 1.0 } else { 0.0 });
+            }
+        }
+    }
+}
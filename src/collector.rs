@@ -0,0 +1,224 @@
+//! Per-subsystem enable flags and refresh cadences for the `sysinfo` collectors.
+//!
+//! Refreshing process, component, CPU, memory, network and disk data every
+//! single second is wasteful: disks and components change slowly and every
+//! `sysinfo` refresh has a real cost on constrained hardware. `CollectorConfig`
+//! lets each subsystem be switched off or harvested on its own interval.
+
+use std::env;
+use std::time::Duration;
+
+use serde::Deserialize;
+use tokio::time::Instant;
+
+/// Enable flag and refresh interval for one collector subsystem.
+#[derive(Clone, Copy)]
+pub struct SubsystemConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+}
+
+/// The `[collector.<subsystem>]` table in the TOML config file; every field is
+/// optional so an omitted table (or omitted field) falls through to defaults.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(default)]
+pub struct SubsystemFileConfig {
+    pub enabled: Option<bool>,
+    pub interval_secs: Option<u64>,
+}
+
+impl SubsystemConfig {
+    /// Resolve a subsystem's settings as defaults -> config file -> env vars,
+    /// where `prefix` names the `<PREFIX>_ENABLED` / `<PREFIX>_INTERVAL_SECS` env vars.
+    fn resolve(
+        prefix: &str,
+        file: SubsystemFileConfig,
+        default_enabled: bool,
+        default_interval: Duration,
+    ) -> Self {
+        let enabled = file.enabled.unwrap_or(default_enabled);
+        let interval = file
+            .interval_secs
+            .map(Duration::from_secs)
+            .unwrap_or(default_interval);
+
+        let enabled = env::var(format!("{}_ENABLED", prefix))
+            .ok()
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(enabled);
+        let interval = env::var(format!("{}_INTERVAL_SECS", prefix))
+            .ok()
+            .and_then(|v| v.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(interval);
+
+        SubsystemConfig { enabled, interval }
+    }
+}
+
+/// The `[collector]` table in the TOML config file.
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(default)]
+pub struct CollectorFileConfig {
+    pub process: SubsystemFileConfig,
+    pub components: SubsystemFileConfig,
+    pub cpu: SubsystemFileConfig,
+    pub memory: SubsystemFileConfig,
+    pub networks: SubsystemFileConfig,
+    pub disks: SubsystemFileConfig,
+    pub battery: SubsystemFileConfig,
+}
+
+/// Enable flags and cadences for every `sysinfo` subsystem `main_loop` collects.
+pub struct CollectorConfig {
+    pub process: SubsystemConfig,
+    pub components: SubsystemConfig,
+    pub cpu: SubsystemConfig,
+    pub memory: SubsystemConfig,
+    pub networks: SubsystemConfig,
+    pub disks: SubsystemConfig,
+    pub battery: SubsystemConfig,
+}
+
+impl CollectorConfig {
+    /// Resolve a `CollectorConfig` from the file's `[collector]` table and env
+    /// vars, falling back to sensible defaults: process/CPU/memory/networks
+    /// every second, components every 10s and disks every 30s, all enabled
+    /// except battery.
+    pub fn resolve(file: CollectorFileConfig) -> Self {
+        CollectorConfig {
+            process: SubsystemConfig::resolve("PROCESS", file.process, true, Duration::from_secs(1)),
+            components: SubsystemConfig::resolve(
+                "COMPONENTS",
+                file.components,
+                true,
+                Duration::from_secs(10),
+            ),
+            cpu: SubsystemConfig::resolve("CPU", file.cpu, true, Duration::from_secs(1)),
+            memory: SubsystemConfig::resolve("MEMORY", file.memory, true, Duration::from_secs(1)),
+            networks: SubsystemConfig::resolve(
+                "NETWORKS",
+                file.networks,
+                true,
+                Duration::from_secs(1),
+            ),
+            disks: SubsystemConfig::resolve("DISKS", file.disks, true, Duration::from_secs(30)),
+            battery: SubsystemConfig::resolve(
+                "BATTERY",
+                file.battery,
+                false,
+                Duration::from_secs(30),
+            ),
+        }
+    }
+}
+
+/// Tracks when a subsystem is next due to run and advances on each harvest.
+pub struct Schedule {
+    next_due: Instant,
+    interval: Duration,
+    pub enabled: bool,
+}
+
+impl Schedule {
+    pub fn new(config: SubsystemConfig, now: Instant) -> Self {
+        Schedule {
+            next_due: now,
+            interval: config.interval,
+            enabled: config.enabled,
+        }
+    }
+
+    /// Whether this subsystem should be harvested right now. If so, the
+    /// schedule is advanced to the next due time.
+    pub fn is_due(&mut self, now: Instant) -> bool {
+        if !self.enabled || now < self.next_due {
+            return false;
+        }
+        self.next_due = now + self.interval;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_uses_defaults_when_nothing_overrides() {
+        let resolved = SubsystemConfig::resolve(
+            "SGP30_EXPORTER_TEST_DEFAULTS",
+            SubsystemFileConfig::default(),
+            true,
+            Duration::from_secs(5),
+        );
+        assert!(resolved.enabled);
+        assert_eq!(resolved.interval, Duration::from_secs(5));
+    }
+
+    #[test]
+    fn resolve_file_overrides_defaults() {
+        let file = SubsystemFileConfig {
+            enabled: Some(false),
+            interval_secs: Some(42),
+        };
+        let resolved = SubsystemConfig::resolve(
+            "SGP30_EXPORTER_TEST_FILE",
+            file,
+            true,
+            Duration::from_secs(5),
+        );
+        assert!(!resolved.enabled);
+        assert_eq!(resolved.interval, Duration::from_secs(42));
+    }
+
+    #[test]
+    fn resolve_env_overrides_file_and_defaults() {
+        let prefix = "SGP30_EXPORTER_TEST_ENV";
+        env::set_var(format!("{}_ENABLED", prefix), "true");
+        env::set_var(format!("{}_INTERVAL_SECS", prefix), "7");
+
+        let file = SubsystemFileConfig {
+            enabled: Some(false),
+            interval_secs: Some(42),
+        };
+        let resolved = SubsystemConfig::resolve(prefix, file, false, Duration::from_secs(5));
+
+        env::remove_var(format!("{}_ENABLED", prefix));
+        env::remove_var(format!("{}_INTERVAL_SECS", prefix));
+
+        assert!(resolved.enabled);
+        assert_eq!(resolved.interval, Duration::from_secs(7));
+    }
+
+    #[test]
+    fn schedule_is_due_advances_only_after_interval_elapses() {
+        let now = Instant::now();
+        let mut schedule = Schedule::new(
+            SubsystemConfig {
+                enabled: true,
+                interval: Duration::from_secs(10),
+            },
+            now,
+        );
+
+        assert!(schedule.is_due(now));
+        assert!(!schedule.is_due(now));
+        assert!(!schedule.is_due(now + Duration::from_secs(9)));
+        assert!(schedule.is_due(now + Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn schedule_is_due_always_false_when_disabled() {
+        let now = Instant::now();
+        let mut schedule = Schedule::new(
+            SubsystemConfig {
+                enabled: false,
+                interval: Duration::from_secs(1),
+            },
+            now,
+        );
+        assert!(!schedule.is_due(now));
+        assert!(!schedule.is_due(now + Duration::from_secs(100)));
+    }
+}
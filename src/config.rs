@@ -0,0 +1,150 @@
+//! TOML configuration file with env-var override precedence.
+//!
+//! Settings resolve as defaults -> config file -> environment variables, so
+//! a bare env-var deployment (the historical behavior) keeps working
+//! unchanged, while a config file lets every setting live in one place.
+
+use std::env;
+use std::error::Error;
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::collector::{CollectorConfig, CollectorFileConfig};
+use crate::filter::{self, FilterFileConfig, FilterList};
+
+const DEFAULT_PORT: &str = "9185";
+const DEFAULT_I2C_DEVICE: &str = "/dev/i2c-1";
+const DEFAULT_SGP30_ADDRESS: u8 = 0x58;
+const DEFAULT_BASELINE_FILE: &str = "sgp30_baseline.dat";
+const DEFAULT_HUMIDITY_URL: &str = "http://raspberrypi5:9521/metrics";
+const DEFAULT_HUMIDITY_DEVICE: &str = "e9:60:94:11:db:5e";
+const DEFAULT_TEMPERATURE_METRIC: &str = "ruuvi_temperature_celsius";
+const DEFAULT_HUMIDITY_METRIC: &str = "ruuvi_humidity_ratio";
+const DEFAULT_CONFIG_FILE: &str = "sgp30-exporter.toml";
+
+/// The full shape of the TOML config file; every field is optional so a
+/// partial (or absent) file still parses and falls through to defaults.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct FileConfig {
+    port: Option<String>,
+    i2c_device: Option<String>,
+    sgp30_address: Option<u8>,
+    baseline_file: Option<String>,
+    humidity_url: Option<String>,
+    humidity_primary_device: Option<String>,
+    humidity_device_pattern: Option<String>,
+    temperature_metric: Option<String>,
+    humidity_metric: Option<String>,
+    net_filter: FilterFileConfig,
+    disk_filter: FilterFileConfig,
+    collector: CollectorFileConfig,
+}
+
+/// Fully resolved exporter configuration.
+pub struct Config {
+    pub port: String,
+    pub i2c_device: String,
+    pub sgp30_address: u8,
+    pub baseline_file: String,
+    pub humidity_url: String,
+    /// The device whose reading feeds the SGP30's humidity compensation.
+    pub humidity_primary_device: String,
+    /// Matches every device whose temperature/humidity should be re-exported.
+    pub humidity_device_filter: FilterList,
+    pub temperature_metric: String,
+    pub humidity_metric: String,
+    pub net_filter: Option<FilterList>,
+    pub disk_filter: Option<FilterList>,
+    pub collector: CollectorConfig,
+}
+
+fn env_string(name: &str, default: String) -> String {
+    env::var(name).unwrap_or(default)
+}
+
+impl Config {
+    /// Load the config file (`--config <path>`, `CONFIG` env var, or
+    /// `sgp30-exporter.toml` if it exists) and layer env vars on top.
+    pub fn load() -> Result<Self, Box<dyn Error>> {
+        let path = env::args()
+            .collect::<Vec<_>>()
+            .windows(2)
+            .find(|w| w[0] == "--config")
+            .map(|w| w[1].clone())
+            .or_else(|| env::var("CONFIG").ok());
+
+        let file: FileConfig = match path {
+            Some(path) => toml::from_str(&fs::read_to_string(&path)?)?,
+            None if fs::metadata(DEFAULT_CONFIG_FILE).is_ok() => {
+                toml::from_str(&fs::read_to_string(DEFAULT_CONFIG_FILE)?)?
+            }
+            None => FileConfig::default(),
+        };
+
+        let net_filter = filter::resolve("NET_FILTER", file.net_filter).transpose()?;
+        let disk_filter = filter::resolve("DISK_FILTER", file.disk_filter).transpose()?;
+
+        let humidity_primary_device = env_string(
+            "HUMIDITY_MAC",
+            file.humidity_primary_device
+                .unwrap_or_else(|| DEFAULT_HUMIDITY_DEVICE.to_string()),
+        );
+        // Without an explicit pattern, only the primary device is exported,
+        // matching the historical single-device behavior.
+        let humidity_device_pattern = env_string(
+            "HUMIDITY_DEVICE_PATTERN",
+            file.humidity_device_pattern
+                .unwrap_or_else(|| humidity_primary_device.clone()),
+        );
+        let humidity_device_filter = FilterList::compile(
+            &[humidity_device_pattern],
+            /* is_list_ignored */ false,
+            /* regex */ true,
+            /* case_sensitive */ false,
+            /* whole_word */ false,
+        )?;
+
+        Ok(Config {
+            port: env_string("PORT", file.port.unwrap_or_else(|| DEFAULT_PORT.to_string())),
+            i2c_device: env_string(
+                "I2C_DEVICE",
+                file.i2c_device.unwrap_or_else(|| DEFAULT_I2C_DEVICE.to_string()),
+            ),
+            sgp30_address: env::var("SGP30_ADDRESS")
+                .ok()
+                .and_then(|v| {
+                    v.strip_prefix("0x")
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                        .or_else(|| v.parse().ok())
+                })
+                .unwrap_or(file.sgp30_address.unwrap_or(DEFAULT_SGP30_ADDRESS)),
+            baseline_file: env_string(
+                "BASELINE_FILE",
+                file.baseline_file
+                    .unwrap_or_else(|| DEFAULT_BASELINE_FILE.to_string()),
+            ),
+            humidity_url: env_string(
+                "HUMIDITY_URL",
+                file.humidity_url
+                    .unwrap_or_else(|| DEFAULT_HUMIDITY_URL.to_string()),
+            ),
+            humidity_primary_device,
+            humidity_device_filter,
+            temperature_metric: env_string(
+                "TEMPERATURE_METRIC",
+                file.temperature_metric
+                    .unwrap_or_else(|| DEFAULT_TEMPERATURE_METRIC.to_string()),
+            ),
+            humidity_metric: env_string(
+                "HUMIDITY_METRIC",
+                file.humidity_metric
+                    .unwrap_or_else(|| DEFAULT_HUMIDITY_METRIC.to_string()),
+            ),
+            net_filter,
+            disk_filter,
+            collector: CollectorConfig::resolve(file.collector),
+        })
+    }
+}
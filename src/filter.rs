@@ -0,0 +1,218 @@
+//! Allow/deny-list filtering for metric label values (interface names, disk names, ...).
+//!
+//! A `FilterList` is compiled once at startup from a handful of patterns and then
+//! cheaply reused on every `main_loop` iteration instead of re-parsing or
+//! re-compiling anything per sample.
+
+use std::env;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// A single compiled pattern, either a regex or a literal comparison.
+enum Matcher {
+    Regex(Regex),
+    Literal {
+        pattern: String,
+        case_sensitive: bool,
+        whole_word: bool,
+    },
+}
+
+impl Matcher {
+    fn is_match(&self, name: &str) -> bool {
+        match self {
+            Matcher::Regex(re) => re.is_match(name),
+            Matcher::Literal {
+                pattern,
+                case_sensitive,
+                whole_word,
+            } => {
+                if *case_sensitive {
+                    if *whole_word {
+                        name == pattern
+                    } else {
+                        name.contains(pattern.as_str())
+                    }
+                } else if *whole_word {
+                    name.eq_ignore_ascii_case(pattern)
+                } else {
+                    name.to_lowercase().contains(&pattern.to_lowercase())
+                }
+            }
+        }
+    }
+}
+
+/// A compiled allow/deny list for a single metric dimension (interfaces, disks, ...).
+pub struct FilterList {
+    matchers: Vec<Matcher>,
+    is_list_ignored: bool,
+}
+
+impl FilterList {
+    /// Compile a filter list from raw patterns and flags.
+    ///
+    /// `is_list_ignored` selects deny-list semantics (matches are dropped) versus
+    /// allow-list semantics (only matches are kept). When `regex` is false each
+    /// pattern is compared literally, honoring `case_sensitive` and `whole_word`.
+    pub fn compile(
+        patterns: &[String],
+        is_list_ignored: bool,
+        regex: bool,
+        case_sensitive: bool,
+        whole_word: bool,
+    ) -> Result<Self, regex::Error> {
+        let matchers = patterns
+            .iter()
+            .map(|pattern| {
+                if regex {
+                    let pattern = if case_sensitive {
+                        pattern.clone()
+                    } else {
+                        format!("(?i){}", pattern)
+                    };
+                    Regex::new(&pattern).map(Matcher::Regex)
+                } else {
+                    Ok(Matcher::Literal {
+                        pattern: pattern.clone(),
+                        case_sensitive,
+                        whole_word,
+                    })
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(FilterList {
+            matchers,
+            is_list_ignored,
+        })
+    }
+
+    /// Whether `name` should be exported as a metric series.
+    pub fn is_allowed(&self, name: &str) -> bool {
+        let matched = self.matchers.iter().any(|m| m.is_match(name));
+        if self.is_list_ignored {
+            !matched
+        } else {
+            matched
+        }
+    }
+}
+
+/// The `[net_filter]` / `[disk_filter]` table in the TOML config file.
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct FilterFileConfig {
+    pub patterns: Vec<String>,
+    pub mode: Option<String>,
+    pub regex: Option<bool>,
+    pub case_sensitive: Option<bool>,
+    pub whole_word: Option<bool>,
+}
+
+/// Resolve a `FilterList` from a config-file table and a family of env vars
+/// sharing `prefix`, as defaults -> config file -> env vars.
+///
+/// `<prefix>` holds the comma-separated patterns (on top of any patterns
+/// already set in the file) and `<prefix>_MODE` (`ignore` or `allow`,
+/// default `ignore`), `<prefix>_REGEX`, `<prefix>_CASE_SENSITIVE` and
+/// `<prefix>_WHOLE_WORD` (booleans, default `true`, `false` and `false`)
+/// override the flags. Returns `None` when neither the file nor `<prefix>`
+/// supply any patterns, meaning no filtering is applied.
+pub fn resolve(prefix: &str, file: FilterFileConfig) -> Option<Result<FilterList, regex::Error>> {
+    let mut patterns = file.patterns;
+    if let Ok(raw) = env::var(prefix) {
+        patterns.extend(
+            raw.split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_owned),
+        );
+    }
+    if patterns.is_empty() {
+        return None;
+    }
+
+    let env_bool = |name: &str, default: bool| {
+        env::var(name)
+            .ok()
+            .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes"))
+            .unwrap_or(default)
+    };
+    let is_list_ignored = env::var(format!("{}_MODE", prefix))
+        .map(|v| v.to_lowercase() != "allow")
+        .unwrap_or_else(|_| file.mode.map(|m| m.to_lowercase() != "allow").unwrap_or(true));
+    let regex = env_bool(&format!("{}_REGEX", prefix), file.regex.unwrap_or(true));
+    let case_sensitive = env_bool(
+        &format!("{}_CASE_SENSITIVE", prefix),
+        file.case_sensitive.unwrap_or(false),
+    );
+    let whole_word = env_bool(
+        &format!("{}_WHOLE_WORD", prefix),
+        file.whole_word.unwrap_or(false),
+    );
+
+    Some(FilterList::compile(
+        &patterns,
+        is_list_ignored,
+        regex,
+        case_sensitive,
+        whole_word,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regex_match_is_case_insensitive_by_default() {
+        let list =
+            FilterList::compile(&["^eth".to_string()], false, true, false, false).unwrap();
+        assert!(list.is_allowed("ETH0"));
+        assert!(!list.is_allowed("wlan0"));
+    }
+
+    #[test]
+    fn regex_match_is_case_sensitive_when_requested() {
+        let list = FilterList::compile(&["^eth".to_string()], false, true, true, false).unwrap();
+        assert!(list.is_allowed("eth0"));
+        assert!(!list.is_allowed("ETH0"));
+    }
+
+    #[test]
+    fn literal_match_is_substring_by_default() {
+        let list = FilterList::compile(&["eth".to_string()], false, false, false, false).unwrap();
+        assert!(list.is_allowed("my-eth0"));
+        assert!(!list.is_allowed("wlan0"));
+    }
+
+    #[test]
+    fn literal_whole_word_requires_exact_match() {
+        let list = FilterList::compile(&["eth0".to_string()], false, false, false, true).unwrap();
+        assert!(list.is_allowed("eth0"));
+        assert!(!list.is_allowed("eth0:1"));
+    }
+
+    #[test]
+    fn literal_case_sensitive_whole_word_is_exact() {
+        let list = FilterList::compile(&["eth0".to_string()], false, false, true, true).unwrap();
+        assert!(list.is_allowed("eth0"));
+        assert!(!list.is_allowed("ETH0"));
+    }
+
+    #[test]
+    fn is_list_ignored_inverts_match_semantics() {
+        let allow_list =
+            FilterList::compile(&["eth0".to_string()], false, false, false, true).unwrap();
+        let deny_list =
+            FilterList::compile(&["eth0".to_string()], true, false, false, true).unwrap();
+
+        assert!(allow_list.is_allowed("eth0"));
+        assert!(!allow_list.is_allowed("wlan0"));
+
+        assert!(!deny_list.is_allowed("eth0"));
+        assert!(deny_list.is_allowed("wlan0"));
+    }
+}
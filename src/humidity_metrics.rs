@@ -0,0 +1,80 @@
+//! Metrics for per-device humidity-source readings and the sensor/fetch health signals.
+//!
+//! Bundled the same way as `BatteryMetrics`: a handful of metrics `main_loop`
+//! only ever touches together, registered behind one constructor each instead
+//! of widening its parameter list further.
+
+use std::error::Error;
+
+use prometheus_exporter::prometheus::{
+    register_counter, register_gauge, register_gauge_vec, Counter, Gauge, GaugeVec,
+};
+
+/// Per-device temperature/humidity gauges re-exported from the humidity source.
+pub struct HumiditySourceMetrics {
+    temperature: GaugeVec,
+    relative_humidity: GaugeVec,
+    absolute_humidity: GaugeVec,
+}
+
+impl HumiditySourceMetrics {
+    pub fn register() -> Result<Self, Box<dyn Error>> {
+        Ok(HumiditySourceMetrics {
+            temperature: register_gauge_vec!(
+                "humidity_source_temperature_celsius",
+                "Temperature reported by a humidity source device",
+                &["device"]
+            )?,
+            relative_humidity: register_gauge_vec!(
+                "humidity_source_relative_humidity_percent",
+                "Relative humidity reported by a humidity source device",
+                &["device"]
+            )?,
+            absolute_humidity: register_gauge_vec!(
+                "humidity_source_absolute_humidity_gm3",
+                "Absolute humidity computed from a humidity source device",
+                &["device"]
+            )?,
+        })
+    }
+
+    /// Record a device's latest temperature/humidity reading.
+    pub fn set(
+        &self,
+        device: &str,
+        temperature: f64,
+        relative_humidity: f64,
+        absolute_humidity: f64,
+    ) {
+        self.temperature.with_label_values(&[device]).set(temperature);
+        self.relative_humidity
+            .with_label_values(&[device])
+            .set(relative_humidity);
+        self.absolute_humidity
+            .with_label_values(&[device])
+            .set(absolute_humidity);
+    }
+}
+
+/// Health signals for the SGP30 sensor and the humidity-source fetch.
+pub struct SensorHealthMetrics {
+    pub up: Gauge,
+    pub measurement_errors: Counter,
+    pub humidity_fetch_errors: Counter,
+}
+
+impl SensorHealthMetrics {
+    pub fn register() -> Result<Self, Box<dyn Error>> {
+        Ok(SensorHealthMetrics {
+            up: register_gauge!("sgp30_up", "1 if the SGP30 sensor is healthy, 0 otherwise")?,
+            measurement_errors: register_counter!(
+                "sgp30_measurement_errors_total",
+                "Total number of failed SGP30 measurements"
+            )?,
+            humidity_fetch_errors: register_counter!(
+                "humidity_fetch_errors_total",
+                "Total number of failed humidity source fetches"
+            )?,
+        })
+    }
+}
@@ -1,14 +1,25 @@
-use std::env;
+mod battery_metrics;
+mod collector;
+mod config;
+mod filter;
+mod humidity_metrics;
+mod sysinfo_metrics;
+
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs::{File, OpenOptions};
 use std::io::{self, Read, Write};
 use std::time::{Duration, SystemTime};
 
+use battery_metrics::BatteryMetrics;
+use collector::Schedule;
+use config::Config;
+use filter::FilterList;
 use hal::{Delay, I2cdev};
+use humidity_metrics::{HumiditySourceMetrics, SensorHealthMetrics};
 use linux_embedded_hal as hal;
 use prometheus_exporter::prometheus::{
-    register_counter, register_counter_vec, register_gauge, register_gauge_vec, register_histogram,
-    Counter, CounterVec, Gauge, GaugeVec, Histogram,
+    register_counter, register_gauge, register_gauge_vec, register_histogram, Gauge, Histogram,
 };
 use prometheus_parse::{Scrape, Value};
 use sgp30::{Baseline, Humidity, Measurement, Sgp30};
@@ -16,21 +27,14 @@ use sysinfo::{
     Components, DiskRefreshKind, Disks, MemoryRefreshKind, Networks, ProcessRefreshKind,
     ProcessesToUpdate, System,
 };
+use sysinfo_metrics::SysinfoMetrics;
 use tokio::signal;
-use tokio::time::{sleep_until, Instant};
-
-const DEFAULT_PORT: &str = "9185";
-const DEFAULT_HUMIDITY_URL: &str = "http://raspberrypi5:9521/metrics";
-const DEFAULT_HUMIDITY_MAC: &str = "e9:60:94:11:db:5e";
-const I2C_DEVICE: &str = "/dev/i2c-1";
-const SGP30_ADDRESS: u8 = 0x58;
-const TEMPERATURE_METRIC: &str = "ruuvi_temperature_celsius";
-const HUMIDITY_METRIC: &str = "ruuvi_humidity_ratio";
-const BASELINE_FILE: &str = "sgp30_baseline.dat";
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, sleep_until, Instant};
 
 /// Load the baseline from a file if available.
-fn load_baseline() -> Option<Baseline> {
-    let mut file = File::open(BASELINE_FILE).ok()?;
+fn load_baseline(baseline_file: &str) -> Option<Baseline> {
+    let mut file = File::open(baseline_file).ok()?;
     let mut content = String::new();
     file.read_to_string(&mut content).ok()?;
 
@@ -42,12 +46,12 @@ fn load_baseline() -> Option<Baseline> {
 }
 
 /// Save the baseline to a file.
-fn save_baseline(baseline: &Baseline) {
+fn save_baseline(baseline_file: &str, baseline: &Baseline) {
     if let Ok(mut file) = OpenOptions::new()
         .write(true)
         .create(true)
         .truncate(true)
-        .open(BASELINE_FILE)
+        .open(baseline_file)
     {
         let _ = writeln!(file, "{} {}", baseline.co2eq, baseline.tvoc);
     }
@@ -63,55 +67,180 @@ fn absolute_humidity(t: f64, rh: f64) -> f64 {
     vapor_pressure(t) * rh * 2.1674 / (273.15 + t)
 }
 
-/// Fetch and parse temperature and humidity metrics from the given URL.
-async fn fetch_humidity_metrics(
-    url: &str,
-    target_device: &str,
-) -> Result<(f64, f64), Box<dyn Error>> {
-    let body = reqwest::get(url).await?.text().await?;
+/// Parse per-device temperature/humidity pairs out of a scraped metrics body.
+///
+/// Only devices matching `device_filter` are kept. A sample missing the
+/// `device` label, or a device missing one of the two metrics, is simply
+/// left out of the result rather than failing the whole parse. An error is
+/// returned only when no matching device has both metrics.
+fn parse_humidity_readings(
+    body: &str,
+    device_filter: &FilterList,
+    temperature_metric: &str,
+    humidity_metric: &str,
+) -> Result<HashMap<String, (f64, f64)>, Box<dyn Error>> {
     let metrics = Scrape::parse(body.lines().map(|s| Ok(s.to_owned())).into_iter())?;
-    let mut temperature = None;
-    let mut humidity = None;
+    let mut readings: HashMap<String, (Option<f64>, Option<f64>)> = HashMap::new();
 
     for sample in metrics.samples {
-        if let Some(device) = sample.labels.get("device") {
-            if device == target_device {
-                match sample.metric.as_str() {
-                    TEMPERATURE_METRIC => {
-                        if let Value::Gauge(v) = sample.value {
-                            temperature = Some(v);
-                        }
-                    }
-                    HUMIDITY_METRIC => {
-                        if let Value::Gauge(v) = sample.value {
-                            humidity = Some(v * 100.0); // Convert ratio to percentage
-                        }
-                    }
-                    _ => {}
-                }
+        let Some(device) = sample.labels.get("device") else {
+            continue;
+        };
+        if !device_filter.is_allowed(device) {
+            continue;
+        }
+        let entry = readings.entry(device.to_string()).or_default();
+        if sample.metric == temperature_metric {
+            if let Value::Gauge(v) = sample.value {
+                entry.0 = Some(v);
+            }
+        } else if sample.metric == humidity_metric {
+            if let Value::Gauge(v) = sample.value {
+                entry.1 = Some(v * 100.0); // Convert ratio to percentage
             }
         }
     }
 
-    match (temperature, humidity) {
-        (Some(t), Some(h)) => Ok((t, h)),
-        _ => Err("Failed to fetch temperature or humidity".into()),
+    let readings: HashMap<String, (f64, f64)> = readings
+        .into_iter()
+        .filter_map(|(device, (t, h))| Some((device, (t?, h?))))
+        .collect();
+
+    if readings.is_empty() {
+        Err("Failed to fetch temperature or humidity for any matching device".into())
+    } else {
+        Ok(readings)
     }
 }
 
-/// Initialize the SGP30 sensor and return its instance.
-async fn initialize_sgp30() -> Result<Sgp30<I2cdev, Delay>, Box<dyn Error>> {
-    let dev = I2cdev::new(I2C_DEVICE)?;
-    let mut sgp = Sgp30::new(dev, SGP30_ADDRESS, Delay);
+/// Fetch and parse per-device temperature/humidity pairs from the given URL.
+async fn fetch_humidity_metrics(
+    url: &str,
+    device_filter: &FilterList,
+    temperature_metric: &str,
+    humidity_metric: &str,
+) -> Result<HashMap<String, (f64, f64)>, Box<dyn Error>> {
+    let body = reqwest::get(url).await?.text().await?;
+    parse_humidity_readings(&body, device_filter, temperature_metric, humidity_metric)
+}
+
+#[cfg(test)]
+mod humidity_parsing_tests {
+    use super::*;
+
+    fn allow_all_devices() -> FilterList {
+        FilterList::compile(&[".*".to_string()], false, true, false, false).unwrap()
+    }
+
+    #[test]
+    fn parses_multiple_distinct_devices() {
+        let body = "\
+# TYPE ruuvi_temperature_celsius gauge
+ruuvi_temperature_celsius{device=\"aa:bb\"} 21.5
+ruuvi_temperature_celsius{device=\"cc:dd\"} 19.0
+# TYPE ruuvi_humidity_ratio gauge
+ruuvi_humidity_ratio{device=\"aa:bb\"} 0.5
+ruuvi_humidity_ratio{device=\"cc:dd\"} 0.6
+";
+        let readings = parse_humidity_readings(
+            body,
+            &allow_all_devices(),
+            "ruuvi_temperature_celsius",
+            "ruuvi_humidity_ratio",
+        )
+        .unwrap();
+
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings["aa:bb"], (21.5, 50.0));
+        assert_eq!(readings["cc:dd"], (19.0, 60.0));
+    }
+
+    #[test]
+    fn ignores_samples_missing_the_device_label() {
+        let body = "\
+# TYPE ruuvi_temperature_celsius gauge
+ruuvi_temperature_celsius 99.9
+ruuvi_temperature_celsius{device=\"aa:bb\"} 21.5
+# TYPE ruuvi_humidity_ratio gauge
+ruuvi_humidity_ratio{device=\"aa:bb\"} 0.5
+";
+        let readings = parse_humidity_readings(
+            body,
+            &allow_all_devices(),
+            "ruuvi_temperature_celsius",
+            "ruuvi_humidity_ratio",
+        )
+        .unwrap();
+
+        assert_eq!(readings.len(), 1);
+        assert_eq!(readings["aa:bb"], (21.5, 50.0));
+    }
 
-    sgp.init().unwrap();
-    let serial_number = sgp.serial().unwrap();
-    let feature_set = sgp.get_feature_set().unwrap();
+    #[test]
+    fn drops_a_device_missing_one_of_the_two_metrics() {
+        let body = "\
+# TYPE ruuvi_temperature_celsius gauge
+ruuvi_temperature_celsius{device=\"ee:ff\"} 18.0
+";
+        let result = parse_humidity_readings(
+            body,
+            &allow_all_devices(),
+            "ruuvi_temperature_celsius",
+            "ruuvi_humidity_ratio",
+        );
 
-    println!("Initializing SGP30 with serial number: {:?}", serial_number);
-    println!("Feature set: {:?}", feature_set);
+        // `ee:ff` is the only device and it is dropped, so nothing is left
+        // and the parse reports an error.
+        assert!(result.is_err());
+    }
+}
 
-    if let Some(baseline) = load_baseline() {
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(30);
+/// Consecutive measurement failures before `main_loop` attempts to reopen the sensor.
+const MEASUREMENT_FAILURE_THRESHOLD: u32 = 5;
+/// Bounded attempts for a mid-run reconnect, unlike the unbounded startup retry.
+const RECONNECT_MAX_ATTEMPTS: u32 = 5;
+
+/// Open the I2C device and run the SGP30's `init` command, without the
+/// diagnostic reads or warm-up loop.
+fn open_sgp30(i2c_device: &str, sgp30_address: u8) -> Result<Sgp30<I2cdev, Delay>, Box<dyn Error>> {
+    let dev = I2cdev::new(i2c_device)?;
+    let mut sgp = Sgp30::new(dev, sgp30_address, Delay);
+    sgp.init().map_err(|e| format!("init failed: {:?}", e))?;
+    Ok(sgp)
+}
+
+/// Open and `init` the SGP30, retrying with bounded exponential backoff on
+/// failure. `max_attempts` bounds how many tries are made; `None` retries
+/// forever, which is appropriate at startup where there is nothing useful
+/// to export without a sensor.
+async fn connect_sgp30(
+    i2c_device: &str,
+    sgp30_address: u8,
+    max_attempts: Option<u32>,
+) -> Result<Sgp30<I2cdev, Delay>, Box<dyn Error>> {
+    let mut attempt = 0;
+    let mut delay = RECONNECT_BASE_DELAY;
+    loop {
+        attempt += 1;
+        match open_sgp30(i2c_device, sgp30_address) {
+            Ok(sgp) => return Ok(sgp),
+            Err(e) => {
+                eprintln!("SGP30 connection attempt {} failed: {}", attempt, e);
+                if max_attempts.is_some_and(|max| attempt >= max) {
+                    return Err(e);
+                }
+                sleep(delay).await;
+                delay = (delay * 2).min(RECONNECT_MAX_DELAY);
+            }
+        }
+    }
+}
+
+/// Restore a saved baseline onto an already-initialized sensor, if one exists.
+fn restore_baseline(sgp: &mut Sgp30<I2cdev, Delay>, baseline_file: &str) {
+    if let Some(baseline) = load_baseline(baseline_file) {
         if let Err(e) = sgp.set_baseline(&baseline) {
             eprintln!("Failed to restore baseline: {:?}", e);
         } else {
@@ -121,6 +250,26 @@ async fn initialize_sgp30() -> Result<Sgp30<I2cdev, Delay>, Box<dyn Error>> {
             );
         }
     }
+}
+
+/// Initialize the SGP30 sensor and return its instance.
+async fn initialize_sgp30(
+    i2c_device: &str,
+    sgp30_address: u8,
+    baseline_file: &str,
+) -> Result<Sgp30<I2cdev, Delay>, Box<dyn Error>> {
+    let mut sgp = connect_sgp30(i2c_device, sgp30_address, None).await?;
+
+    match sgp.serial() {
+        Ok(serial_number) => println!("Initializing SGP30 with serial number: {:?}", serial_number),
+        Err(e) => eprintln!("Failed to read serial number: {:?}", e),
+    }
+    match sgp.get_feature_set() {
+        Ok(feature_set) => println!("Feature set: {:?}", feature_set),
+        Err(e) => eprintln!("Failed to read feature set: {:?}", e),
+    }
+
+    restore_baseline(&mut sgp, baseline_file);
 
     let mut i: u8 = 0;
     loop {
@@ -165,27 +314,22 @@ fn update_metrics(tvoc: &Gauge, co2eq: &Gauge, last_updated: &Gauge, measurement
 }
 
 /// Main loop to fetch humidity metrics and update the SGP30 sensor.
+#[allow(clippy::too_many_arguments)]
 async fn main_loop(
     sgp: &mut Sgp30<I2cdev, Delay>,
     tvoc: &Gauge,
     co2eq: &Gauge,
     last_updated: &Gauge,
-    process_cpu_seconds: &Counter,
-    process_resident_memory_bytes: &Gauge,
-    sysinfo_temperature: &GaugeVec,
-    sysinfo_cpu_usage: &GaugeVec,
-    sysinfo_memory_total_bytes: &Gauge,
-    sysinfo_memory_used_bytes: &Gauge,
-    sysinfo_network_bytes_sent: &CounterVec,
-    sysinfo_network_bytes_received: &CounterVec,
-    sysinfo_disk_total_bytes: &GaugeVec,
-    sysinfo_disk_available_bytes: &GaugeVec,
-    sysinfo_disk_read_bytes: &CounterVec,
-    sysinfo_disk_write_bytes: &CounterVec,
+    sysinfo: &SysinfoMetrics,
+    humidity_metrics: &HumiditySourceMetrics,
+    health: &SensorHealthMetrics,
     loop_duration: &Histogram,
-    url: &str,
-    target_device: &str,
+    config: &Config,
+    battery_metrics: Option<&BatteryMetrics>,
 ) -> Result<(), Box<dyn Error>> {
+    let network_filter = config.net_filter.as_ref();
+    let disk_filter = config.disk_filter.as_ref();
+    let collector_config = &config.collector;
     let mut sys = System::new();
     let mut components = Components::new_with_refreshed_list();
     let mut networks = Networks::new_with_refreshed_list();
@@ -195,103 +339,242 @@ async fn main_loop(
     let mut last_time = Instant::now();
     let mut sleep_target = Instant::now();
     let mut i: u16 = 0;
+    let mut consecutive_measurement_failures: u32 = 0;
+    // Runs the bounded reconnect burst on its own task so a sensor outage
+    // doesn't stall the other collectors, which all share this loop.
+    let mut reconnect_task: Option<JoinHandle<Result<Sgp30<I2cdev, Delay>, String>>> = None;
+    health.up.set(1.0);
+
+    let mut process_schedule = Schedule::new(collector_config.process, sleep_target);
+    let mut components_schedule = Schedule::new(collector_config.components, sleep_target);
+    let mut cpu_schedule = Schedule::new(collector_config.cpu, sleep_target);
+    let mut memory_schedule = Schedule::new(collector_config.memory, sleep_target);
+    let mut networks_schedule = Schedule::new(collector_config.networks, sleep_target);
+    let mut disks_schedule = Schedule::new(collector_config.disks, sleep_target);
+    let mut battery_schedule = Schedule::new(collector_config.battery, sleep_target);
 
     loop {
         sleep_target = sleep_target + Duration::from_secs(1);
         let timer = loop_duration.start_timer();
+        let now = Instant::now();
+
+        // pick up a finished background reconnect without blocking on it
+        if reconnect_task.as_ref().is_some_and(JoinHandle::is_finished) {
+            match reconnect_task.take().unwrap().await {
+                Ok(Ok(mut new_sgp)) => {
+                    restore_baseline(&mut new_sgp, &config.baseline_file);
+                    *sgp = new_sgp;
+                    consecutive_measurement_failures = 0;
+                    health.up.set(1.0);
+                    println!("Sensor reconnected");
+                }
+                Ok(Err(e)) => {
+                    eprintln!("Failed to reconnect sensor: {}", e);
+                    // Let failures accumulate again before the next burst,
+                    // instead of retrying back-to-back forever.
+                    consecutive_measurement_failures = 0;
+                }
+                Err(e) => eprintln!("Reconnect task panicked: {:?}", e),
+            }
+        }
 
-        // update system metrics
-        sys.refresh_processes_specifics(
-            ProcessesToUpdate::Some(&[pid]),
-            true,
-            ProcessRefreshKind::nothing().with_cpu().with_memory(),
-        );
-        if let Some(process) = sys.process(pid) {
-            let now = Instant::now();
-            let elapsed = now.duration_since(last_time).as_secs_f64();
-            let cpu_usage = (process.cpu_usage() / 100.0) as f64; // Convert percentage to fraction
-            process_cpu_seconds.inc_by(cpu_usage * elapsed);
-            process_resident_memory_bytes.set(process.memory() as f64);
-            last_time = now;
+        // update system metrics, each subsystem on its own configured cadence
+        if process_schedule.is_due(now) {
+            sys.refresh_processes_specifics(
+                ProcessesToUpdate::Some(&[pid]),
+                true,
+                ProcessRefreshKind::nothing().with_cpu().with_memory(),
+            );
+            if let Some(process) = sys.process(pid) {
+                let elapsed = now.duration_since(last_time).as_secs_f64();
+                let cpu_usage = (process.cpu_usage() / 100.0) as f64; // Convert percentage to fraction
+                if let Some(process_cpu_seconds) = &sysinfo.process_cpu_seconds {
+                    process_cpu_seconds.inc_by(cpu_usage * elapsed);
+                }
+                if let Some(process_resident_memory_bytes) = &sysinfo.process_resident_memory_bytes {
+                    process_resident_memory_bytes.set(process.memory() as f64);
+                }
+                last_time = now;
+            }
         }
-        components.refresh(true);
-        for component in &components {
-            if let Some(temperature) = component.temperature() {
-                sysinfo_temperature
-                    .with_label_values(&[component.label()])
-                    .set(temperature as f64);
+        if components_schedule.is_due(now) {
+            components.refresh(true);
+            if let Some(sysinfo_temperature) = &sysinfo.temperature {
+                for component in &components {
+                    if let Some(temperature) = component.temperature() {
+                        sysinfo_temperature
+                            .with_label_values(&[component.label()])
+                            .set(temperature as f64);
+                    }
+                }
             }
         }
-        sys.refresh_cpu_usage();
-        for cpu in sys.cpus() {
-            sysinfo_cpu_usage
-                .with_label_values(&[cpu.name()])
-                .set(cpu.cpu_usage() as f64);
+        if cpu_schedule.is_due(now) {
+            sys.refresh_cpu_usage();
+            if let Some(sysinfo_cpu_usage) = &sysinfo.cpu_usage {
+                for cpu in sys.cpus() {
+                    sysinfo_cpu_usage
+                        .with_label_values(&[cpu.name()])
+                        .set(cpu.cpu_usage() as f64);
+                }
+            }
         }
-        sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
-        sysinfo_memory_total_bytes.set(sys.total_memory() as f64);
-        sysinfo_memory_used_bytes.set(sys.used_memory() as f64);
-        networks.refresh(true);
-        for (interface_name, data) in &networks {
-            sysinfo_network_bytes_sent
-                .with_label_values(&[interface_name])
-                .inc_by(data.transmitted() as f64);
-            sysinfo_network_bytes_received
-                .with_label_values(&[interface_name])
-                .inc_by(data.received() as f64);
+        if memory_schedule.is_due(now) {
+            sys.refresh_memory_specifics(MemoryRefreshKind::nothing().with_ram());
+            if let (Some(sysinfo_memory_total_bytes), Some(sysinfo_memory_used_bytes)) =
+                (&sysinfo.memory_total_bytes, &sysinfo.memory_used_bytes)
+            {
+                sysinfo_memory_total_bytes.set(sys.total_memory() as f64);
+                sysinfo_memory_used_bytes.set(sys.used_memory() as f64);
+            }
         }
-        disks.refresh_specifics(true, DiskRefreshKind::everything());
-        for disk in &disks {
-            let disk_name = disk.name().to_str().unwrap_or("unknown");
-            sysinfo_disk_total_bytes
-                .with_label_values(&[disk_name])
-                .set(disk.total_space() as f64);
-            sysinfo_disk_available_bytes
-                .with_label_values(&[disk_name])
-                .set(disk.available_space() as f64);
-            let usage = disk.usage();
-            sysinfo_disk_read_bytes
-                .with_label_values(&[disk_name])
-                .inc_by(usage.read_bytes as f64);
-            sysinfo_disk_write_bytes
-                .with_label_values(&[disk_name])
-                .inc_by(usage.written_bytes as f64);
+        if networks_schedule.is_due(now) {
+            networks.refresh(true);
+            if let (Some(sysinfo_network_bytes_sent), Some(sysinfo_network_bytes_received)) = (
+                &sysinfo.network_bytes_sent,
+                &sysinfo.network_bytes_received,
+            ) {
+                for (interface_name, data) in &networks {
+                    if !network_filter.is_none_or(|f| f.is_allowed(interface_name)) {
+                        continue;
+                    }
+                    sysinfo_network_bytes_sent
+                        .with_label_values(&[interface_name])
+                        .inc_by(data.transmitted() as f64);
+                    sysinfo_network_bytes_received
+                        .with_label_values(&[interface_name])
+                        .inc_by(data.received() as f64);
+                }
+            }
+        }
+        if disks_schedule.is_due(now) {
+            disks.refresh_specifics(true, DiskRefreshKind::everything());
+            if let (
+                Some(sysinfo_disk_total_bytes),
+                Some(sysinfo_disk_available_bytes),
+                Some(sysinfo_disk_read_bytes),
+                Some(sysinfo_disk_write_bytes),
+            ) = (
+                &sysinfo.disk_total_bytes,
+                &sysinfo.disk_available_bytes,
+                &sysinfo.disk_read_bytes,
+                &sysinfo.disk_write_bytes,
+            ) {
+                for disk in &disks {
+                    let disk_name = disk.name().to_str().unwrap_or("unknown");
+                    if !disk_filter.is_none_or(|f| f.is_allowed(disk_name)) {
+                        continue;
+                    }
+                    sysinfo_disk_total_bytes
+                        .with_label_values(&[disk_name])
+                        .set(disk.total_space() as f64);
+                    sysinfo_disk_available_bytes
+                        .with_label_values(&[disk_name])
+                        .set(disk.available_space() as f64);
+                    let usage = disk.usage();
+                    sysinfo_disk_read_bytes
+                        .with_label_values(&[disk_name])
+                        .inc_by(usage.read_bytes as f64);
+                    sysinfo_disk_write_bytes
+                        .with_label_values(&[disk_name])
+                        .inc_by(usage.written_bytes as f64);
+                }
+            }
+        }
+        if battery_schedule.is_due(now) {
+            if let Some(battery_metrics) = battery_metrics {
+                battery_metrics.update();
+            }
         }
 
         if (i % 60) == 0 {
-            match fetch_humidity_metrics(url, target_device).await {
-                Ok((temperature, relative_humidity)) => {
-                    let now = SystemTime::now()
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-
-                    let abs_humidity = absolute_humidity(temperature, relative_humidity);
-                    if let Ok(h_abs) = Humidity::from_f32(abs_humidity as f32) {
-                        if let Err(e) = sgp.set_humidity(Some(&h_abs)) {
-                            eprintln!("Failed to set humidity: {:?}", e);
-                        } else {
-                            println!(
-                                "{}: Fetched metrics - Temperature: {:.2} °C, Humidity: {:.2} % / {:.2} g/m³",
-                                now, temperature, relative_humidity, abs_humidity
-                            );
+            match fetch_humidity_metrics(
+                &config.humidity_url,
+                &config.humidity_device_filter,
+                &config.temperature_metric,
+                &config.humidity_metric,
+            )
+            .await
+            {
+                Ok(readings) => {
+                    for (device, (temperature, relative_humidity)) in &readings {
+                        let abs_humidity = absolute_humidity(*temperature, *relative_humidity);
+                        humidity_metrics.set(
+                            device.as_str(),
+                            *temperature,
+                            *relative_humidity,
+                            abs_humidity,
+                        );
+                    }
+
+                    if let Some((temperature, relative_humidity)) =
+                        readings.get(&config.humidity_primary_device)
+                    {
+                        let now = SystemTime::now()
+                            .duration_since(SystemTime::UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs();
+
+                        let abs_humidity = absolute_humidity(*temperature, *relative_humidity);
+                        if let Ok(h_abs) = Humidity::from_f32(abs_humidity as f32) {
+                            if let Err(e) = sgp.set_humidity(Some(&h_abs)) {
+                                eprintln!("Failed to set humidity: {:?}", e);
+                            } else {
+                                println!(
+                                    "{}: Fetched metrics - Temperature: {:.2} °C, Humidity: {:.2} % / {:.2} g/m³",
+                                    now, temperature, relative_humidity, abs_humidity
+                                );
+                            }
                         }
+                    } else {
+                        eprintln!(
+                            "Primary humidity device {:?} not present in this fetch",
+                            config.humidity_primary_device
+                        );
                     }
                 }
-                Err(e) => eprintln!("Failed to fetch humidity metrics: {:?}", e),
+                Err(e) => {
+                    eprintln!("Failed to fetch humidity metrics: {:?}", e);
+                    health.humidity_fetch_errors.inc();
+                }
             }
         }
 
         match sgp.measure() {
-            Ok(measurement) => update_metrics(tvoc, co2eq, last_updated, &measurement),
-            Err(e) => eprintln!("Measurement failed: {:?}", e),
+            Ok(measurement) => {
+                consecutive_measurement_failures = 0;
+                health.up.set(1.0);
+                update_metrics(tvoc, co2eq, last_updated, &measurement);
+            }
+            Err(e) => {
+                eprintln!("Measurement failed: {:?}", e);
+                health.measurement_errors.inc();
+                consecutive_measurement_failures += 1;
+
+                if consecutive_measurement_failures >= MEASUREMENT_FAILURE_THRESHOLD
+                    && reconnect_task.is_none()
+                {
+                    eprintln!(
+                        "{} consecutive measurement failures, reconnecting the sensor in the background",
+                        consecutive_measurement_failures
+                    );
+                    health.up.set(0.0);
+                    let i2c_device = config.i2c_device.clone();
+                    let sgp30_address = config.sgp30_address;
+                    reconnect_task = Some(tokio::spawn(async move {
+                        connect_sgp30(&i2c_device, sgp30_address, Some(RECONNECT_MAX_ATTEMPTS))
+                            .await
+                            .map_err(|e| e.to_string())
+                    }));
+                }
+            }
         }
 
         // Save baseline every 10 minutes
         if i % 600 == 599 {
             match sgp.get_baseline() {
                 Ok(baseline) => {
-                    save_baseline(&baseline);
+                    save_baseline(&config.baseline_file, &baseline);
                     println!(
                         "Saved baseline - CO₂eq: {}, TVOC: {}",
                         baseline.co2eq, baseline.tvoc
@@ -317,8 +600,9 @@ async fn shutdown_signal() {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
-    let port = env::var("PORT").unwrap_or_else(|_| DEFAULT_PORT.to_string());
-    let binding = format!("0.0.0.0:{}", port).parse()?;
+    let config = Config::load()?;
+
+    let binding = format!("0.0.0.0:{}", config.port).parse()?;
     let _exporter = prometheus_exporter::start(binding)?;
 
     let last_updated = register_gauge!("sgp30_last_updated", "Last update timestamp")?;
@@ -326,49 +610,19 @@ async fn main() -> Result<(), Box<dyn Error>> {
         "process_start_time_seconds",
         "Process start time in seconds"
     )?;
-    let process_cpu_seconds_total = register_counter!(
-        "process_cpu_seconds_total",
-        "Total CPU seconds consumed by the process"
-    )?;
-    let process_resident_memory_bytes = register_gauge!(
-        "process_resident_memory_bytes",
-        "Size of resident memory set in bytes"
-    )?;
-    let sysinfo_temperature =
-        register_gauge_vec!("sysinfo_temperature", "Temperature in °C", &["label"])?;
-    let sysinfo_cpu_usage =
-        register_gauge_vec!("sysinfo_cpu_usage", "CPU usage in percentage", &["name"])?;
-    let sysinfo_memory_total_bytes =
-        register_gauge!("sysinfo_memory_total_bytes", "Total memory in bytes")?;
-    let sysinfo_memory_used_bytes =
-        register_gauge!("sysinfo_memory_used_bytes", "Used memory in bytes")?;
-    let sysinfo_network_bytes_sent =
-        register_counter_vec!("sysinfo_network_bytes_sent", "Bytes sent", &["interface"])?;
-    let sysinfo_network_bytes_received = register_counter_vec!(
-        "sysinfo_network_bytes_received",
-        "Bytes received",
-        &["interface"]
-    )?;
-    let sysinfo_disk_read_bytes =
-        register_counter_vec!("sysinfo_disk_read_bytes", "Bytes read", &["disk"])?;
-    let sysinfo_disk_write_bytes =
-        register_counter_vec!("sysinfo_disk_write_bytes", "Bytes written", &["disk"])?;
-    let sysinfo_disk_total_bytes =
-        register_gauge_vec!("sysinfo_disk_total_bytes", "Total disk space", &["disk"])?;
-    let sysinfo_disk_available_bytes = register_gauge_vec!(
-        "sysinfo_disk_available_bytes",
-        "Available disk space",
-        &["disk"]
-    )?;
+    let sysinfo_metrics = SysinfoMetrics::register(&config.collector)?;
     let loop_duration = register_histogram!(
         "loop_duration",
         "duration of SGP30 measurement loop in seconds"
     )?;
+    let humidity_metrics = HumiditySourceMetrics::register()?;
 
     let tvoc = register_gauge!("sgp30_tvoc", "TVOC in ppb")?;
     let co2eq = register_gauge!("sgp30_co2eq", "CO₂eq in ppm")?;
     co2eq.set(400 as f64);
 
+    let health_metrics = SensorHealthMetrics::register()?;
+
     let now = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)?
         .as_secs();
@@ -387,13 +641,20 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .unwrap()
         .set(1.);
 
-    println!("Exporter listening on port: {}", port);
+    println!("Exporter listening on port: {}", config.port);
 
-    let url = env::var("HUMIDITY_URL").unwrap_or_else(|_| DEFAULT_HUMIDITY_URL.to_string());
-    let target_device =
-        env::var("HUMIDITY_MAC").unwrap_or_else(|_| DEFAULT_HUMIDITY_MAC.to_string());
+    let battery_metrics = if config.collector.battery.enabled {
+        Some(BatteryMetrics::register()?)
+    } else {
+        None
+    };
 
-    let mut sgp = initialize_sgp30().await?;
+    let mut sgp = initialize_sgp30(
+        &config.i2c_device,
+        config.sgp30_address,
+        &config.baseline_file,
+    )
+    .await?;
 
     tokio::select! {
         _ = main_loop(
@@ -401,21 +662,12 @@ async fn main() -> Result<(), Box<dyn Error>> {
             &tvoc,
             &co2eq,
             &last_updated,
-            &process_cpu_seconds_total,
-            &process_resident_memory_bytes,
-            &sysinfo_temperature,
-            &sysinfo_cpu_usage,
-            &sysinfo_memory_total_bytes,
-            &sysinfo_memory_used_bytes,
-            &sysinfo_network_bytes_sent,
-            &sysinfo_network_bytes_received,
-            &sysinfo_disk_total_bytes,
-            &sysinfo_disk_available_bytes,
-            &sysinfo_disk_read_bytes,
-            &sysinfo_disk_write_bytes,
+            &sysinfo_metrics,
+            &humidity_metrics,
+            &health_metrics,
             &loop_duration,
-            &url,
-            &target_device,
+            &config,
+            battery_metrics.as_ref(),
         ) => {},
         _ = shutdown_signal() => {},
     }
@@ -0,0 +1,143 @@
+//! Registered `sysinfo`-backed metric handles, gated per-subsystem by `CollectorConfig`.
+//!
+//! Each field is `Some` only when its subsystem is enabled, so a disabled
+//! subsystem is skipped at registration time too, not just at harvest time -
+//! one `Option` field per subsystem instead of `main_loop` taking a raw
+//! parameter per metric.
+
+use std::error::Error;
+
+use prometheus_exporter::prometheus::{
+    register_counter, register_counter_vec, register_gauge, register_gauge_vec, Counter,
+    CounterVec, Gauge, GaugeVec,
+};
+
+use crate::collector::CollectorConfig;
+
+/// Registered `sysinfo` metric handles, one `Option` field per subsystem.
+pub struct SysinfoMetrics {
+    pub process_cpu_seconds: Option<Counter>,
+    pub process_resident_memory_bytes: Option<Gauge>,
+    pub temperature: Option<GaugeVec>,
+    pub cpu_usage: Option<GaugeVec>,
+    pub memory_total_bytes: Option<Gauge>,
+    pub memory_used_bytes: Option<Gauge>,
+    pub network_bytes_sent: Option<CounterVec>,
+    pub network_bytes_received: Option<CounterVec>,
+    pub disk_total_bytes: Option<GaugeVec>,
+    pub disk_available_bytes: Option<GaugeVec>,
+    pub disk_read_bytes: Option<CounterVec>,
+    pub disk_write_bytes: Option<CounterVec>,
+}
+
+impl SysinfoMetrics {
+    /// Register each subsystem's metrics only if `collector` has it enabled.
+    pub fn register(collector: &CollectorConfig) -> Result<Self, Box<dyn Error>> {
+        Ok(SysinfoMetrics {
+            process_cpu_seconds: if collector.process.enabled {
+                Some(register_counter!(
+                    "process_cpu_seconds_total",
+                    "Total CPU seconds consumed by the process"
+                )?)
+            } else {
+                None
+            },
+            process_resident_memory_bytes: if collector.process.enabled {
+                Some(register_gauge!(
+                    "process_resident_memory_bytes",
+                    "Size of resident memory set in bytes"
+                )?)
+            } else {
+                None
+            },
+            temperature: if collector.components.enabled {
+                Some(register_gauge_vec!(
+                    "sysinfo_temperature",
+                    "Temperature in °C",
+                    &["label"]
+                )?)
+            } else {
+                None
+            },
+            cpu_usage: if collector.cpu.enabled {
+                Some(register_gauge_vec!(
+                    "sysinfo_cpu_usage",
+                    "CPU usage in percentage",
+                    &["name"]
+                )?)
+            } else {
+                None
+            },
+            memory_total_bytes: if collector.memory.enabled {
+                Some(register_gauge!(
+                    "sysinfo_memory_total_bytes",
+                    "Total memory in bytes"
+                )?)
+            } else {
+                None
+            },
+            memory_used_bytes: if collector.memory.enabled {
+                Some(register_gauge!(
+                    "sysinfo_memory_used_bytes",
+                    "Used memory in bytes"
+                )?)
+            } else {
+                None
+            },
+            network_bytes_sent: if collector.networks.enabled {
+                Some(register_counter_vec!(
+                    "sysinfo_network_bytes_sent",
+                    "Bytes sent",
+                    &["interface"]
+                )?)
+            } else {
+                None
+            },
+            network_bytes_received: if collector.networks.enabled {
+                Some(register_counter_vec!(
+                    "sysinfo_network_bytes_received",
+                    "Bytes received",
+                    &["interface"]
+                )?)
+            } else {
+                None
+            },
+            disk_total_bytes: if collector.disks.enabled {
+                Some(register_gauge_vec!(
+                    "sysinfo_disk_total_bytes",
+                    "Total disk space",
+                    &["disk"]
+                )?)
+            } else {
+                None
+            },
+            disk_available_bytes: if collector.disks.enabled {
+                Some(register_gauge_vec!(
+                    "sysinfo_disk_available_bytes",
+                    "Available disk space",
+                    &["disk"]
+                )?)
+            } else {
+                None
+            },
+            disk_read_bytes: if collector.disks.enabled {
+                Some(register_counter_vec!(
+                    "sysinfo_disk_read_bytes",
+                    "Bytes read",
+                    &["disk"]
+                )?)
+            } else {
+                None
+            },
+            disk_write_bytes: if collector.disks.enabled {
+                Some(register_counter_vec!(
+                    "sysinfo_disk_write_bytes",
+                    "Bytes written",
+                    &["disk"]
+                )?)
+            } else {
+                None
+            },
+        })
+    }
+}